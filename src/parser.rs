@@ -24,10 +24,22 @@ pub struct Parser {
     scanner: Scanner,
     current: Token,
     next: Token,
+    loop_depth: usize,
+    repl: bool,
 }
 
 impl Parser {
     pub fn new(source: &str) -> Self {
+        Self::with_repl(source, false)
+    }
+
+    /// Create a parser in REPL mode, where a trailing expression without a
+    /// `;` at end of input is accepted as an expression statement.
+    pub fn new_repl(source: &str) -> Self {
+        Self::with_repl(source, true)
+    }
+
+    fn with_repl(source: &str, repl: bool) -> Self {
         let mut scanner = Scanner::new(&source);
         let current = scanner.next().unwrap();
         let next = scanner.next().unwrap();
@@ -35,6 +47,20 @@ impl Parser {
             scanner,
             current,
             next,
+            loop_depth: 0,
+            repl,
+        }
+    }
+
+    /// Parse exactly one declaration, returning `None` at end of input.
+    ///
+    /// This lets a REPL feed lines as they arrive instead of batching the
+    /// whole program through `parse_program`.
+    pub fn parse_statement(&mut self) -> Result<Option<AstNode>, ParsingError> {
+        if self.current.kind == Kind::Eof {
+            Ok(None)
+        } else {
+            self.declaration().map(Some)
         }
     }
 
@@ -60,6 +86,23 @@ impl Parser {
         }
     }
 
+    /// Parse the source and return the program serialized as JSON.
+    ///
+    /// Because every node threads a `Span`, the serialized form keeps
+    /// `start`/`end` offsets on each node, giving external tooling
+    /// (formatters, linters, editor plugins) round-trippable source
+    /// locations without linking the crate.
+    pub fn parse_program_json(&mut self) -> Result<String, Vec<ParsingError>> {
+        let program = self.parse_program()?;
+        Ok(serde_json::to_string(&program).expect("AstNode is serializable"))
+    }
+
+    /// Reconstruct a program from its JSON representation, the inverse of
+    /// [`Parser::parse_program_json`].
+    pub fn program_from_json(json: &str) -> Result<Vec<AstNode>, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
     fn declaration(&mut self) -> Result<AstNode, ParsingError> {
         match self.current.kind {
             Kind::Var => self.var_declaration(),
@@ -67,6 +110,7 @@ impl Parser {
                 self.advance();
                 self.function()
             }
+            Kind::Class => self.class_declaration(),
             _ => self.statement(),
         }
     }
@@ -103,6 +147,44 @@ impl Parser {
         ))
     }
 
+    fn class_declaration(&mut self) -> Result<AstNode, ParsingError> {
+        let keyword = self.advance();
+        let (name, _) = self.id_token()?;
+
+        let superclass = if self.current.kind == Kind::Less {
+            self.advance();
+            let (super_name, super_span) = self.id_token()?;
+            Some(Box::new(AstNode::new_expression(
+                Expression::Variable { name: super_name },
+                super_span,
+            )))
+        } else {
+            None
+        };
+
+        self.eat(Kind::LeftBrace, "Expected '{' before class body.")?;
+
+        let mut methods = vec![];
+        loop {
+            match self.current.kind {
+                Kind::RightBrace | Kind::Eof => break,
+                _ => methods.push(self.function()?),
+            }
+        }
+
+        let rbrace = self.eat(Kind::RightBrace, "Expected '}' after class body.")?;
+        let span = Span::merge(vec![&keyword.span, &rbrace.span]);
+
+        Ok(AstNode::new_statement(
+            Statement::ClassDeclaration {
+                name,
+                superclass,
+                methods,
+            },
+            span,
+        ))
+    }
+
     fn parameter_list(&mut self) -> Result<Vec<Token>, ParsingError> {
         let mut parameters = vec![];
         parameters.push(self.advance());
@@ -138,7 +220,12 @@ impl Parser {
         };
 
         self.eat(Kind::RightParen, "Expected ')' after formal parameter list")?;
-        let body = self.block_statement()?;
+
+        let enclosing_depth = self.loop_depth;
+        self.loop_depth = 0;
+        let body = self.block_statement();
+        self.loop_depth = enclosing_depth;
+        let body = body?;
         let span = Span::merge(vec![&name_span, &body.span]);
 
         Ok(AstNode::new_statement(
@@ -159,12 +246,25 @@ impl Parser {
             Kind::While => self.while_statement(),
             Kind::For => self.for_statement(),
             Kind::Return => self.return_statement(),
+            Kind::Break => self.break_statement(),
+            Kind::Continue => self.continue_statement(),
             _ => self.expression_statement(),
         }
     }
 
     fn expression_statement(&mut self) -> Result<AstNode, ParsingError> {
         let expression = self.expression()?;
+
+        if self.repl && self.current.kind == Kind::Eof {
+            let new_span = expression.span;
+            return Ok(AstNode::new_statement(
+                Statement::Expression {
+                    expression: Box::new(expression),
+                },
+                new_span,
+            ));
+        }
+
         let semi = self.eat(Kind::Semicolon, "Expected ';' after expression")?;
         let new_span = Span::merge(vec![&expression.span, &semi.span]);
         Ok(AstNode::new_statement(
@@ -191,6 +291,32 @@ impl Parser {
         Ok(AstNode::new_statement(Statement::Return { value }, span))
     }
 
+    fn break_statement(&mut self) -> Result<AstNode, ParsingError> {
+        let keyword = self.advance();
+        if self.loop_depth == 0 {
+            return Err(ParsingError {
+                message: "'break' outside of loop.".to_string(),
+                span: keyword.span,
+            });
+        }
+        let semi = self.eat(Kind::Semicolon, "Expected ';' after 'break'.")?;
+        let span = Span::merge(vec![&keyword.span, &semi.span]);
+        Ok(AstNode::new_statement(Statement::Break, span))
+    }
+
+    fn continue_statement(&mut self) -> Result<AstNode, ParsingError> {
+        let keyword = self.advance();
+        if self.loop_depth == 0 {
+            return Err(ParsingError {
+                message: "'continue' outside of loop.".to_string(),
+                span: keyword.span,
+            });
+        }
+        let semi = self.eat(Kind::Semicolon, "Expected ';' after 'continue'.")?;
+        let span = Span::merge(vec![&keyword.span, &semi.span]);
+        Ok(AstNode::new_statement(Statement::Continue, span))
+    }
+
     fn for_statement(&mut self) -> Result<AstNode, ParsingError> {
         let keyword = self.advance();
         self.eat(Kind::LeftParen, "Expected '(' after 'for.'")?;
@@ -218,7 +344,10 @@ impl Parser {
 
         self.eat(Kind::RightParen, "Expected ')' before for block.")?;
 
-        let block = self.statement()?;
+        self.loop_depth += 1;
+        let block = self.statement();
+        self.loop_depth -= 1;
+        let block = block?;
         let span = Span::merge(vec![&keyword.span, &block.span]);
 
         Ok(AstNode::new_statement(
@@ -239,7 +368,10 @@ impl Parser {
         let condition = self.expression()?;
         self.eat(Kind::RightParen, "Expected ')' after while condition.")?;
 
-        let block = self.statement()?;
+        self.loop_depth += 1;
+        let block = self.statement();
+        self.loop_depth -= 1;
+        let block = block?;
         let span = Span::merge(vec![&keyword.span, &block.span]);
 
         Ok(AstNode::new_statement(
@@ -327,6 +459,28 @@ impl Parser {
             let rvalue = self.assignment()?;
             let new_span = Span::merge(vec![&node.span, &operator.span, &rvalue.span]);
 
+            if let Some(Expression::Get { object, name }) = node.as_expression() {
+                return Ok(AstNode::new_expression(
+                    Expression::Set {
+                        object: object.clone(),
+                        name: name.clone(),
+                        value: Box::new(rvalue),
+                    },
+                    new_span,
+                ));
+            }
+
+            if let Some(Expression::Index { target, index }) = node.as_expression() {
+                return Ok(AstNode::new_expression(
+                    Expression::IndexSet {
+                        target: target.clone(),
+                        index: index.clone(),
+                        value: Box::new(rvalue),
+                    },
+                    new_span,
+                ));
+            }
+
             Ok(AstNode::new_expression(
                 Expression::Assignment {
                     lvalue: Box::new(node),
@@ -341,11 +495,41 @@ impl Parser {
     }
 
     fn logic_or(&mut self) -> Result<AstNode, ParsingError> {
-        self.logic_and()
+        let mut node = self.logic_and()?;
+        while self.current.kind == Kind::Or {
+            let operator = self.advance();
+            let right = self.logic_and()?;
+            let new_span = Span::merge(vec![&node.span, &operator.span, &right.span]);
+
+            node = AstNode::new_expression(
+                Expression::Logical {
+                    left: Box::new(node),
+                    operator,
+                    right: Box::new(right),
+                },
+                new_span,
+            );
+        }
+        Ok(node)
     }
 
     fn logic_and(&mut self) -> Result<AstNode, ParsingError> {
-        self.equality()
+        let mut node = self.equality()?;
+        while self.current.kind == Kind::And {
+            let operator = self.advance();
+            let right = self.equality()?;
+            let new_span = Span::merge(vec![&node.span, &operator.span, &right.span]);
+
+            node = AstNode::new_expression(
+                Expression::Logical {
+                    left: Box::new(node),
+                    operator,
+                    right: Box::new(right),
+                },
+                new_span,
+            );
+        }
+        Ok(node)
     }
 
     fn equality(&mut self) -> Result<AstNode, ParsingError> {
@@ -449,6 +633,22 @@ impl Parser {
         }
     }
 
+    /// Parse zero or more comma-separated expressions up to `terminator`,
+    /// allowing both an empty list and a trailing comma.
+    fn comma_list(&mut self, terminator: Kind) -> Result<Vec<AstNode>, ParsingError> {
+        let mut elements = vec![];
+        while self.current.kind != terminator {
+            elements.push(self.expression()?);
+            if self.current.kind == Kind::Comma {
+                self.advance();
+            } else {
+                break;
+            }
+        }
+
+        Ok(elements)
+    }
+
     fn argument_list(&mut self) -> Result<Vec<AstNode>, ParsingError> {
         let mut args = vec![];
         args.push(self.expression()?);
@@ -461,30 +661,61 @@ impl Parser {
     }
 
     fn call(&mut self) -> Result<AstNode, ParsingError> {
-        let primary = self.primary()?;
-
-        if self.current.kind == Kind::LeftParen {
-            self.advance();
-
-            let arguments = match self.current.kind {
-                Kind::RightParen => vec![],
-                _ => self.argument_list()?,
-            };
+        let mut node = self.primary()?;
 
-            let rparen = self.eat(Kind::RightParen, "Expected ')' after argument list.")?;
-
-            let new_span = Span::merge(vec![&primary.span, &rparen.span]);
+        loop {
+            match self.current.kind {
+                Kind::LeftParen => {
+                    self.advance();
 
-            Ok(AstNode::new_expression(
-                Expression::Call {
-                    target: Box::new(primary),
-                    arguments,
-                },
-                new_span,
-            ))
-        } else {
-            Ok(primary)
+                    let arguments = match self.current.kind {
+                        Kind::RightParen => vec![],
+                        _ => self.argument_list()?,
+                    };
+
+                    let rparen = self.eat(Kind::RightParen, "Expected ')' after argument list.")?;
+                    let new_span = Span::merge(vec![&node.span, &rparen.span]);
+
+                    node = AstNode::new_expression(
+                        Expression::Call {
+                            target: Box::new(node),
+                            arguments,
+                        },
+                        new_span,
+                    );
+                }
+                Kind::Dot => {
+                    self.advance();
+                    let (name, name_span) = self.id_token()?;
+                    let new_span = Span::merge(vec![&node.span, &name_span]);
+
+                    node = AstNode::new_expression(
+                        Expression::Get {
+                            object: Box::new(node),
+                            name,
+                        },
+                        new_span,
+                    );
+                }
+                Kind::LeftBracket => {
+                    self.advance();
+                    let index = self.expression()?;
+                    let rbracket = self.eat(Kind::RightBracket, "Expected ']' after index.")?;
+                    let new_span = Span::merge(vec![&node.span, &rbracket.span]);
+
+                    node = AstNode::new_expression(
+                        Expression::Index {
+                            target: Box::new(node),
+                            index: Box::new(index),
+                        },
+                        new_span,
+                    );
+                }
+                _ => break,
+            }
         }
+
+        Ok(node)
     }
 
     fn primary(&mut self) -> Result<AstNode, ParsingError> {
@@ -522,6 +753,59 @@ impl Parser {
                     span,
                 ))
             }
+            Kind::LeftBracket => {
+                let lbracket = self.advance();
+                let elements = self.comma_list(Kind::RightBracket)?;
+                let rbracket = self.eat(Kind::RightBracket, "Expected ']' after list literal.")?;
+                let span = Span::merge(vec![&lbracket.span, &rbracket.span]);
+                Ok(AstNode::new_expression(
+                    Expression::ListLiteral { elements },
+                    span,
+                ))
+            }
+            Kind::Fun => {
+                let keyword = self.advance();
+                self.eat(Kind::LeftParen, "Expected '(' after 'fun'.")?;
+
+                let parameters = match self.current.kind {
+                    Kind::RightParen => vec![],
+                    Kind::IdentifierLiteral(_) => self.parameter_list()?,
+                    _ => {
+                        return Err(ParsingError {
+                            message: "Expected parameter list or ')'.".to_string(),
+                            span: self.current.span,
+                        })
+                    }
+                };
+
+                self.eat(Kind::RightParen, "Expected ')' after formal parameter list")?;
+
+                let enclosing_depth = self.loop_depth;
+                self.loop_depth = 0;
+                let body = self.block_statement();
+                self.loop_depth = enclosing_depth;
+                let body = body?;
+                let span = Span::merge(vec![&keyword.span, &body.span]);
+
+                Ok(AstNode::new_expression(
+                    Expression::Lambda {
+                        parameters,
+                        body: Box::new(body),
+                    },
+                    span,
+                ))
+            }
+            Kind::This => Ok(AstNode::new_expression(
+                Expression::This,
+                self.advance().span,
+            )),
+            Kind::Super => {
+                let keyword = self.advance();
+                self.eat(Kind::Dot, "Expected '.' after 'super'.")?;
+                let (method, method_span) = self.id_token()?;
+                let span = Span::merge(vec![&keyword.span, &method_span]);
+                Ok(AstNode::new_expression(Expression::Super { method }, span))
+            }
             _ => Err(ParsingError {
                 span: self.current.span,
                 message: "Expected primary expression.".to_string(),